@@ -3,10 +3,14 @@
 
 extern crate alloc;
 
+use core::cell::RefCell;
 use core::num::NonZeroUsize;
 
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 use alloc::vec::Vec;
+
+use rusttype::{point, Font, GlyphId, Scale};
 use bitfield_struct::bitfield;
 
 use nx::gpu::surface::Surface;
@@ -60,7 +64,7 @@ mod config {
 }
 
 /// One point rectangle
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Rect {
     pub left: usize,
     pub top: usize,
@@ -178,6 +182,213 @@ impl Color {
     }
 }
 
+/// Per-slot dirty-rectangle bookkeeping for the 2-buffer swapchain.
+///
+/// `dequeue_buffer` hands back the two slots alternately, so each buffer is a
+/// frame behind the other. We track which regions each slot still has to
+/// repaint to catch up to the latest content, plus the regions touched in the
+/// frame currently being drawn, so that a present repaints only the regions
+/// recorded for that buffer. Repaints are recorded like any other draw, so a
+/// recorded region is repainted again on every later frame; once a slot's
+/// first frame has repainted the whole layer, that is the whole layer.
+#[derive(Default)]
+struct DirtyTracker {
+    /// Regions each slot must repaint to catch up. Index by slot (0/1).
+    pending: [Vec<Rect>; 2],
+    /// Regions touched in the in-progress frame.
+    current: Vec<Rect>,
+    /// Whether each slot has ever been drawn; a fresh slot needs a full repaint.
+    initialized: [bool; 2],
+}
+
+impl DirtyTracker {
+    /// Record that `rect` changed this frame, skipping empties and rects already
+    /// wholly covered by a rect recorded earlier this frame to keep the list small.
+    fn mark(&mut self, rect: Rect) {
+        if rect.is_empty() {
+            return;
+        }
+        if self
+            .current
+            .iter()
+            .any(|r| r.intersect(rect).width == rect.width && r.intersect(rect).height == rect.height)
+        {
+            return;
+        }
+        self.current.push(rect);
+    }
+
+    /// Regions that must be repainted into `slot` before drawing this frame.
+    /// A slot drawn for the first time repaints everything (`full`).
+    fn take_pending(&mut self, slot: usize, full: Rect) -> Vec<Rect> {
+        if !self.initialized[slot] {
+            self.initialized[slot] = true;
+            return alloc::vec::Vec::from([full]);
+        }
+        core::mem::take(&mut self.pending[slot])
+    }
+
+    /// Finalize the frame drawn into `slot`: the other buffer now lags behind by
+    /// everything drawn this frame.
+    fn commit(&mut self, slot: usize) {
+        let other = slot ^ 1;
+        self.pending[other].extend(self.current.drain(..));
+    }
+}
+
+/// Runtime-configurable palette and layer geometry. Every colour the UI draws
+/// with is read from the active `Theme` rather than a `const` on [`Color`], so
+/// overlays can be restyled without recompiling. [`Theme::default`] reproduces
+/// the original hardcoded libtesla look.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub background: Color,
+    pub transparent: Color,
+    pub highlight: Color,
+    pub frame: Color,
+    pub handle: Color,
+    pub text: Color,
+    pub description: Color,
+    pub header_bar: Color,
+    pub click_animation: Color,
+
+    /// Width of the Tesla layer, consumed by [`Renderer::new`] when it creates
+    /// the managed layer surface.
+    pub layer_width: u16,
+    /// Height of the Tesla layer, consumed by [`Renderer::new`].
+    pub layer_height: u16,
+    /// X position of the Tesla layer, consumed by [`Renderer::new`].
+    pub layer_pos_x: u16,
+    /// Y position of the Tesla layer, consumed by [`Renderer::new`].
+    pub layer_pos_y: u16,
+    /// Overlay activation key combo.
+    pub launch_combo: u64,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: Color::BACKGROUND,
+            transparent: Color::TRANSPARENT,
+            highlight: Color::HIGHLIGHT,
+            frame: Color::FRAME,
+            handle: Color::HANDLE,
+            text: Color::TEXT,
+            description: Color::DESCRIPTION,
+            header_bar: Color::HEADER_BAR,
+            click_animation: Color::CLICK_ANIMATION,
+            layer_width: 448,
+            layer_height: 720,
+            layer_pos_x: 0,
+            layer_pos_y: 0,
+            launch_combo: 0,
+        }
+    }
+}
+
+impl Theme {
+    /// Build a theme from a `key=value` config blob, one entry per line. Unknown
+    /// or malformed lines are ignored and any key left out keeps its default,
+    /// so a partial config only overrides what it names. Colour values are four
+    /// hex nibbles in `rgba` order (e.g. `highlight=0fdf`); geometry values are
+    /// decimal and `launch_combo` is a decimal `u64`.
+    pub fn from_config(blob: &[u8]) -> Self {
+        let mut theme = Self::default();
+        let Ok(text) = core::str::from_utf8(blob) else {
+            return theme;
+        };
+
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+
+            match key {
+                "background" => set_color(&mut theme.background, value),
+                "transparent" => set_color(&mut theme.transparent, value),
+                "highlight" => set_color(&mut theme.highlight, value),
+                "frame" => set_color(&mut theme.frame, value),
+                "handle" => set_color(&mut theme.handle, value),
+                "text" => set_color(&mut theme.text, value),
+                "description" => set_color(&mut theme.description, value),
+                "header_bar" => set_color(&mut theme.header_bar, value),
+                "click_animation" => set_color(&mut theme.click_animation, value),
+                "layer_width" => set_u16(&mut theme.layer_width, value),
+                "layer_height" => set_u16(&mut theme.layer_height, value),
+                "layer_pos_x" => set_u16(&mut theme.layer_pos_x, value),
+                "layer_pos_y" => set_u16(&mut theme.layer_pos_y, value),
+                "launch_combo" => {
+                    if let Ok(v) = value.parse() {
+                        theme.launch_combo = v;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        theme
+    }
+}
+
+/// Parse four hex nibbles in `rgba` order into `slot`, leaving it untouched on
+/// a malformed value.
+fn set_color(slot: &mut Color, value: &str) {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    let mut nibbles = [0u8; 4];
+    let mut chars = value.chars();
+    for n in nibbles.iter_mut() {
+        match chars.next().and_then(|c| c.to_digit(16)) {
+            Some(d) => *n = d as u8,
+            None => return,
+        }
+    }
+    if chars.next().is_some() {
+        return;
+    }
+    *slot = Color::from_values(nibbles[0], nibbles[1], nibbles[2], nibbles[3]);
+}
+
+fn set_u16(slot: &mut u16, value: &str) {
+    if let Ok(v) = value.parse() {
+        *slot = v;
+    }
+}
+
+/// Identifies which font in the renderer's fallback chain a cached glyph was
+/// rasterized from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FontId {
+    /// The Latin base font tried first.
+    Standard,
+    /// The system/local-language font (e.g. CJK ranges).
+    Local,
+    /// The user-supplied extended font (e.g. emoji).
+    External,
+}
+
+/// A glyph that has already been rasterized to an 8-bit coverage bitmap, kept
+/// around so that a static label isn't re-rasterized every frame.
+struct CachedGlyph {
+    /// Coverage bitmap, `width * height` bytes, 0x00..=0xFF alpha.
+    coverage: Vec<u8>,
+    width: usize,
+    height: usize,
+    /// Offset from the pen position to the top-left of the bitmap.
+    bearing_x: i32,
+    bearing_y: i32,
+    /// Horizontal pen advance for this glyph at the requested scale.
+    advance: f32,
+}
+
+/// Key into the renderer's glyph cache: the font the glyph came from, its id
+/// within that font, and the raster scale (as raw `f32` bits so it is `Ord`).
+type GlyphCacheKey = (FontId, u16, u32);
+
 /// Direction in which focus moved before landing on the currently focused element
 /// Keeping compatibility from ambiguous left/right in original libtesla
 #[repr(C)]
@@ -196,6 +407,7 @@ pub enum FocusDirection {
 
 /// Current input control mode
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
     /// Input from controller
     Controller,
@@ -205,6 +417,244 @@ pub enum InputMode {
     TouchScroll,
 }
 
+/// A higher-level touch event distilled from the raw per-frame `TouchState`
+/// stream by the [`GestureRecognizer`].
+#[derive(Debug, Clone, Copy)]
+pub enum Gesture {
+    /// Press and release within a small radius and short time.
+    Tap { x: usize, y: usize },
+    /// Held past the long-press threshold without moving.
+    LongPress { x: usize, y: usize },
+    /// A decaying scroll impulse produced on release, re-emitted each frame
+    /// while the fling decays. Velocity is in pixels per frame.
+    Fling { x: usize, y: usize, vx: f32, vy: f32 },
+}
+
+/// State of the touch currently in progress.
+struct ActiveTouch {
+    finger_id: u32,
+    start: (i32, i32),
+    last: (i32, i32),
+    /// Frames the finger has been down, used as a coarse timer.
+    frames_held: u32,
+    /// Set once the finger travels past the movement slop.
+    moved: bool,
+    /// Whether a long-press has already been emitted for this touch.
+    long_press_sent: bool,
+    /// Whether the press began over a registered hitbox.
+    started_in_hitbox: bool,
+    /// Smoothed per-frame velocity.
+    vx: f32,
+    vy: f32,
+}
+
+/// A fling impulse decaying toward rest.
+struct FlingState {
+    x: usize,
+    y: usize,
+    vx: f32,
+    vy: f32,
+}
+
+/// Interprets the raw per-frame `TouchState` stream into taps, long-presses and
+/// flings, and reports the input mode so scrollable containers can consume
+/// fling momentum. Owned by the [`Renderer`].
+#[derive(Default)]
+pub struct GestureRecognizer {
+    active: Option<ActiveTouch>,
+    fling: Option<FlingState>,
+    input_mode: InputMode,
+}
+
+impl Default for InputMode {
+    fn default() -> Self {
+        InputMode::Controller
+    }
+}
+
+impl GestureRecognizer {
+    /// Squared movement slop (px²) beyond which a press is no longer a tap.
+    const MOVE_SLOP_SQ: i32 = 20 * 20;
+    /// Frames a stationary press must be held to register as a long press.
+    const LONG_PRESS_FRAMES: u32 = 30;
+    /// Per-frame multiplier applied to fling velocity.
+    const FLING_DECAY: f32 = 0.85;
+    /// Minimum release speed (px/frame) to start a fling.
+    const FLING_MIN: f32 = 2.0;
+    /// Speed below which a decaying fling stops.
+    const FLING_STOP: f32 = 0.5;
+
+    /// Current input mode: `TouchScroll` while a fling decays, `Touch` while a
+    /// finger is down, else `Controller`.
+    pub fn input_mode(&self) -> InputMode {
+        self.input_mode
+    }
+
+    /// Feed one frame's raw touch sample (or `None` on release) and whether the
+    /// touch point currently lies over a hitbox. Returns the gesture produced
+    /// this frame, if any.
+    pub fn update(&mut self, touch: Option<(u32, u32, u32)>, in_hitbox: bool) -> Option<Gesture> {
+        match touch {
+            Some((finger_id, x, y)) => self.on_sample(finger_id, x as i32, y as i32, in_hitbox),
+            None => self.on_release(),
+        }
+    }
+
+    fn on_sample(&mut self, finger_id: u32, x: i32, y: i32, in_hitbox: bool) -> Option<Gesture> {
+        self.input_mode = InputMode::Touch;
+        self.fling = None;
+
+        // A changed finger id mid-gesture is a new, unrelated touch.
+        let restart = match &self.active {
+            Some(a) => a.finger_id != finger_id,
+            None => true,
+        };
+        if restart {
+            self.active = Some(ActiveTouch {
+                finger_id,
+                start: (x, y),
+                last: (x, y),
+                frames_held: 0,
+                moved: false,
+                long_press_sent: false,
+                started_in_hitbox: in_hitbox,
+                vx: 0.0,
+                vy: 0.0,
+            });
+            return None;
+        }
+
+        let active = self.active.as_mut().unwrap();
+        let (dx, dy) = (x - active.last.x(), y - active.last.y());
+        // Exponential moving average keeps the fling velocity stable against
+        // single noisy samples.
+        active.vx = active.vx * 0.6 + dx as f32 * 0.4;
+        active.vy = active.vy * 0.6 + dy as f32 * 0.4;
+        active.last = (x, y);
+        active.frames_held += 1;
+
+        let travel = {
+            let (sx, sy) = (x - active.start.x(), y - active.start.y());
+            sx * sx + sy * sy
+        };
+        if travel > Self::MOVE_SLOP_SQ {
+            active.moved = true;
+        }
+
+        if !active.moved
+            && !active.long_press_sent
+            && active.started_in_hitbox
+            && active.frames_held >= Self::LONG_PRESS_FRAMES
+        {
+            active.long_press_sent = true;
+            return Some(Gesture::LongPress {
+                x: x as usize,
+                y: y as usize,
+            });
+        }
+
+        None
+    }
+
+    fn on_release(&mut self) -> Option<Gesture> {
+        if let Some(active) = self.active.take() {
+            let (x, y) = (active.last.x().max(0) as usize, active.last.y().max(0) as usize);
+            let speed = active.vx.abs() + active.vy.abs();
+
+            if active.moved && speed >= Self::FLING_MIN {
+                self.fling = Some(FlingState {
+                    x,
+                    y,
+                    vx: active.vx,
+                    vy: active.vy,
+                });
+                self.input_mode = InputMode::TouchScroll;
+                return self.decay_fling();
+            }
+
+            self.input_mode = InputMode::Controller;
+            if !active.moved && active.long_press_sent {
+                // Already reported as a long press; nothing more on release.
+                return None;
+            }
+            if !active.moved && active.started_in_hitbox {
+                return Some(Gesture::Tap { x, y });
+            }
+            return None;
+        }
+
+        // No finger down: keep decaying any in-flight fling.
+        if self.fling.is_some() {
+            self.input_mode = InputMode::TouchScroll;
+            return self.decay_fling();
+        }
+        self.input_mode = InputMode::Controller;
+        None
+    }
+
+    fn decay_fling(&mut self) -> Option<Gesture> {
+        let fling = self.fling.as_mut()?;
+        let gesture = Gesture::Fling {
+            x: fling.x,
+            y: fling.y,
+            vx: fling.vx,
+            vy: fling.vy,
+        };
+        fling.vx *= Self::FLING_DECAY;
+        fling.vy *= Self::FLING_DECAY;
+        if fling.vx.abs() + fling.vy.abs() < Self::FLING_STOP {
+            self.fling = None;
+            self.input_mode = InputMode::Controller;
+        }
+        Some(gesture)
+    }
+}
+
+/// Accessors spelling out the tuple coordinate layout for readability.
+trait Point2 {
+    fn x(&self) -> i32;
+    fn y(&self) -> i32;
+}
+impl Point2 for (i32, i32) {
+    fn x(&self) -> i32 {
+        self.0
+    }
+    fn y(&self) -> i32 {
+        self.1
+    }
+}
+
+/// A registered input region for the current frame.
+///
+/// Hitboxes are inserted during the layout pass in tree order, so a later
+/// insertion is drawn (and therefore touched) on top of an earlier one. The
+/// `z` field records that insertion order so hit-testing can resolve overlaps
+/// deterministically without re-walking the tree.
+#[derive(Debug, Clone, Copy)]
+pub struct Hitbox {
+    pub rect: Rect,
+    pub id: usize,
+    pub z: usize,
+}
+
+/// Context handed to every [`elm::Element::layout`] implementation during the
+/// pre-paint layout pass. Elements register the screen regions they want to
+/// receive input for by calling [`LayoutCtx::push`]; the renderer later queries
+/// the accumulated list to decide which single element owns a given pixel.
+pub struct LayoutCtx<'c> {
+    hitboxes: &'c mut Vec<Hitbox>,
+}
+
+impl<'c> LayoutCtx<'c> {
+    /// Register `rect` as belonging to the element identified by `id`. The
+    /// z-order is assigned from the current insertion position so that elements
+    /// visited later in the tree (drawn last) win overlapping hit-tests.
+    pub fn push(&mut self, id: usize, rect: Rect) {
+        let z = self.hitboxes.len();
+        self.hitboxes.push(Hitbox { rect, id, z });
+    }
+}
+
 ///Combo key mapping
 pub struct KeyInfo {
     key: DebugPadButton,
@@ -216,16 +666,35 @@ impl KeyInfo {}
 
 pub struct Renderer<'r> {
     pub opacity: f32,
+    /// Active palette and layer geometry used by every draw path.
+    pub theme: Theme,
     display_handle: nx::gpu::Context,
     pub(crate) surface: Surface,
-    scisoring_config: Vec<Rect>,
+    /// Stack of active scissoring rectangles. Behind a `RefCell` so containers
+    /// can push/pop a clip through the `FrameBuffer`'s shared `&Renderer`
+    /// borrow while drawing their children.
+    scisoring_config: RefCell<Vec<Rect>>,
+    /// Hit regions registered during the current frame's layout pass, in tree
+    /// (insertion) order. Cleared at the start of every layout pass.
+    hitboxes: Vec<Hitbox>,
     standard_font: Option<rusttype::Font<'r>>,
     local_font: Option<rusttype::Font<'r>>,
     external_font: Option<rusttype::Font<'r>>,
+    /// Rasterized-glyph cache shared across frames. Behind a `RefCell` because
+    /// text is drawn through the `FrameBuffer`'s shared `&Renderer` borrow.
+    glyph_cache: RefCell<BTreeMap<GlyphCacheKey, CachedGlyph>>,
+    /// Dirty-region tracker driving per-slot double-buffer reconciliation.
+    dirty: RefCell<DirtyTracker>,
+    /// Touch gesture recognizer fed the raw per-frame `TouchState` stream.
+    gestures: GestureRecognizer,
 }
 
 impl<'r> Renderer<'r> {
-    pub fn new(x: f32, y: f32, width: u32, height: u32, opacity: f32) -> Result<Self> {
+    /// Create the renderer and its managed layer surface at the position and
+    /// size given by `theme`'s `layer_pos_x/y`/`layer_width/height`, so a
+    /// theme loaded via [`Theme::from_config`] controls the actual on-screen
+    /// layer rather than only the palette.
+    pub fn new(theme: Theme, opacity: f32) -> Result<Self> {
         //nx::hbl::AbiConfigEntry
         let mut gpu_ctx = gpu::Context::new(
             gpu::NvDrvServiceKind::Applet,
@@ -236,10 +705,10 @@ impl<'r> Renderer<'r> {
             "Default",
             0,
             LayerFlags::Default(),
-            x,
-            y,
-            width,
-            height,
+            theme.layer_pos_x as f32,
+            theme.layer_pos_y as f32,
+            theme.layer_width as u32,
+            theme.layer_height as u32,
             gpu::LayerZ::Max,
             2,
             gpu::ColorFormat::R4G4B4A4,
@@ -249,12 +718,17 @@ impl<'r> Renderer<'r> {
 
         Ok(Self {
             opacity,
+            theme,
             display_handle: gpu_ctx,
             surface,
-            scisoring_config: Vec::new(),
+            scisoring_config: RefCell::new(Vec::new()),
+            hitboxes: Vec::new(),
             standard_font: None,
             local_font: None,
             external_font: None,
+            glyph_cache: RefCell::new(BTreeMap::new()),
+            dirty: RefCell::new(DirtyTracker::default()),
+            gestures: GestureRecognizer::default(),
         })
     }
 
@@ -272,13 +746,210 @@ impl<'r> Renderer<'r> {
             .with_a({ color.a() as f32 * self.opacity } as u8)
     }
 
+    /// Run the pre-paint layout pass over `root`, rebuilding the per-frame
+    /// hitbox list. This must be called before drawing so that touch/focus
+    /// dispatch reflects this frame's geometry rather than the previous one's.
+    pub fn layout(&mut self, root: &mut dyn elm::Element) {
+        self.hitboxes.clear();
+        let mut ctx = LayoutCtx {
+            hitboxes: &mut self.hitboxes,
+        };
+        root.layout(&mut ctx);
+    }
+
+    /// Resolve the element that owns pixel `(x, y)`, returning its id. The
+    /// topmost (last-inserted) hitbox whose `Rect` contains the point wins, so
+    /// a modal registered after the list underneath it shadows that list.
+    pub fn hit_test(&self, x: usize, y: usize) -> Option<usize> {
+        self.hitboxes
+            .iter()
+            .filter(|hb| hb.rect.contains(x, y))
+            .max_by_key(|hb| hb.z)
+            .map(|hb| hb.id)
+    }
+
+    /// Feed this frame's raw touch sample to the gesture recognizer and return
+    /// any higher-level gesture it produced. Whether the touch lies over a
+    /// hitbox is resolved against the current layout pass, so a press starting
+    /// outside every element is recognised as such.
+    pub fn update_gestures(&mut self, touch: Option<&TouchState>) -> Option<Gesture> {
+        let sample = touch.map(|t| (t.finger_id, t.x, t.y));
+        let in_hitbox = sample
+            .map(|(_, x, y)| self.hit_test(x as usize, y as usize).is_some())
+            .unwrap_or(false);
+        self.gestures.update(sample, in_hitbox)
+    }
+
+    /// Current input mode as reported by the gesture recognizer.
+    pub fn input_mode(&self) -> InputMode {
+        self.gestures.input_mode()
+    }
+
+    /// Route a recognised gesture to the element tree rooted at `root`. Every
+    /// gesture carries the point it originated at, which is resolved through
+    /// [`Self::hit_test`] so the event reaches the single topmost element
+    /// under that point rather than any overlapped one or the root itself.
+    /// A container may take a gesture in place of the hit element: a
+    /// [`elm::List`] claims every fling that lands inside its bounds.
+    pub fn dispatch_gesture(&self, root: &mut dyn elm::Element, gesture: Gesture) -> bool {
+        let (x, y) = match gesture {
+            Gesture::Tap { x, y } | Gesture::LongPress { x, y } | Gesture::Fling { x, y, .. } => {
+                (x, y)
+            }
+        };
+        match self.hit_test(x, y) {
+            Some(id) => root.handle_gesture(id, gesture),
+            None => false,
+        }
+    }
+
+    /// Parse a serialized layout tree from `data` and instantiate the element
+    /// tree through `loader`, returning the root element. Returns `None` if the
+    /// data is malformed or the root node's type tag is unregistered; a `List`
+    /// child with an unregistered tag is skipped, not reported.
+    pub fn load_layout(
+        &self,
+        data: &[u8],
+        loader: &elm::Loader,
+    ) -> Option<Box<dyn elm::Element>> {
+        loader.load(data)
+    }
+
+    fn font_for(&self, id: FontId) -> Option<&Font<'r>> {
+        match id {
+            FontId::Standard => self.standard_font.as_ref(),
+            FontId::Local => self.local_font.as_ref(),
+            FontId::External => self.external_font.as_ref(),
+        }
+    }
+
+    /// Walk the fallback chain and return the first font that has a real (non
+    /// `.notdef`) glyph for `c`. If no font covers the character, fall back to
+    /// the `.notdef` of the first font that exists so something is still drawn.
+    fn resolve_glyph(&self, c: char) -> Option<(FontId, GlyphId)> {
+        const CHAIN: [FontId; 3] = [FontId::Standard, FontId::Local, FontId::External];
+        for id in CHAIN {
+            if let Some(font) = self.font_for(id) {
+                let gid = font.glyph(c).id();
+                if gid.0 != 0 {
+                    return Some((id, gid));
+                }
+            }
+        }
+        for id in CHAIN {
+            if let Some(font) = self.font_for(id) {
+                return Some((id, font.glyph(c).id()));
+            }
+        }
+        None
+    }
+
+    /// Vertical advance between baselines for the first available font.
+    fn line_advance(&self, scale: Scale) -> f32 {
+        const CHAIN: [FontId; 3] = [FontId::Standard, FontId::Local, FontId::External];
+        for id in CHAIN {
+            if let Some(font) = self.font_for(id) {
+                let v = font.v_metrics(scale);
+                return v.ascent - v.descent + v.line_gap;
+            }
+        }
+        scale.y
+    }
+
+    /// Ascent of the first available font, i.e. the distance from the pen's
+    /// top edge down to the baseline.
+    fn ascent(&self, scale: Scale) -> f32 {
+        const CHAIN: [FontId; 3] = [FontId::Standard, FontId::Local, FontId::External];
+        for id in CHAIN {
+            if let Some(font) = self.font_for(id) {
+                return font.v_metrics(scale).ascent;
+            }
+        }
+        scale.y
+    }
+
+    /// Descent of the first available font, i.e. the distance the baseline sits
+    /// above the bottom of the line. Negative, per rusttype's convention.
+    fn descent(&self, scale: Scale) -> f32 {
+        const CHAIN: [FontId; 3] = [FontId::Standard, FontId::Local, FontId::External];
+        for id in CHAIN {
+            if let Some(font) = self.font_for(id) {
+                return font.v_metrics(scale).descent;
+            }
+        }
+        0.0
+    }
+
+    fn rasterize_glyph(&self, font_id: FontId, gid: GlyphId, scale: Scale) -> CachedGlyph {
+        let font = self.font_for(font_id).expect("font resolved but now missing");
+        let glyph = font.glyph(gid).scaled(scale).positioned(point(0.0, 0.0));
+        let advance = glyph.unpositioned().h_metrics().advance_width;
+
+        if let Some(bb) = glyph.pixel_bounding_box() {
+            let width = (bb.max.x - bb.min.x) as usize;
+            let height = (bb.max.y - bb.min.y) as usize;
+            let mut coverage = Vec::new();
+            coverage.resize(width * height, 0u8);
+            glyph.draw(|x, y, v| {
+                coverage[y as usize * width + x as usize] = (v * 255.0) as u8;
+            });
+            CachedGlyph {
+                coverage,
+                width,
+                height,
+                bearing_x: bb.min.x,
+                bearing_y: bb.min.y,
+                advance,
+            }
+        } else {
+            // Whitespace and zero-coverage glyphs still carry an advance.
+            CachedGlyph {
+                coverage: Vec::new(),
+                width: 0,
+                height: 0,
+                bearing_x: 0,
+                bearing_y: 0,
+                advance,
+            }
+        }
+    }
+
+    /// Resolve `c` through the cache, rasterizing and inserting it on a miss,
+    /// then hand the cached glyph to `f`. Returns `None` only if no font is set.
+    fn with_glyph<R>(&self, c: char, scale: Scale, f: impl FnOnce(&CachedGlyph) -> R) -> Option<R> {
+        let (font_id, gid) = self.resolve_glyph(c)?;
+        let key = (font_id, gid.0, scale.x.to_bits());
+        if self.glyph_cache.borrow().get(&key).is_none() {
+            let glyph = self.rasterize_glyph(font_id, gid, scale);
+            self.glyph_cache.borrow_mut().insert(key, glyph);
+        }
+        let cache = self.glyph_cache.borrow();
+        Some(f(cache.get(&key).expect("just inserted")))
+    }
+
     fn get_framebuffer(&'r mut self) -> Result<FrameBuffer<'r>> {
         let (buffer, buffer_length, slot, fence_present, fences) =
             self.surface.dequeue_buffer(false)?;
 
+        let width = self.surface.get_width() as usize;
+        let height = self.surface.get_height() as usize;
+        // Work out which regions this (possibly stale) buffer must repaint to
+        // reach the latest content before the caller draws this frame.
+        let full = Rect {
+            left: 0,
+            top: 0,
+            width,
+            height,
+        };
+        let dirty_repaint = self
+            .dirty
+            .borrow_mut()
+            .take_pending((slot as usize) & 1, full);
+
         Ok(FrameBuffer {
-            width: self.surface.get_width() as usize,
-            height: self.surface.get_height() as usize,
+            width,
+            height,
+            dirty_repaint,
             buffer: unsafe {
                 core::slice::from_raw_parts_mut(
                     buffer as _,
@@ -296,6 +967,9 @@ impl<'r> Renderer<'r> {
 pub struct FrameBuffer<'b> {
     pub width: usize,
     pub height: usize,
+    /// Regions this buffer must repaint to reconcile with the latest content,
+    /// computed at dequeue time. Consumed by [`FrameBuffer::clear`].
+    pub dirty_repaint: Vec<Rect>,
     pub buffer: &'b mut [Color],
     pub context_ref: &'b Renderer<'b>,
     pub fence_present: bool,
@@ -307,27 +981,59 @@ impl<'b> FrameBuffer<'b> {
     fn stride_item_count(&self) -> usize {
         self.context_ref.surface.compute_stride() as usize / core::mem::size_of::<Color>()
     }
+    /// Repaint the background over the regions this buffer lags behind on; the
+    /// fill is skipped when `dirty_repaint` is empty. The repaint goes through
+    /// [`Self::draw_rect`] and so is itself marked dirty and carried to the
+    /// other buffer: after a slot's first full-layer repaint, every later clear
+    /// is a full-layer fill as well.
     pub fn clear(&mut self) {
-        self.draw_rect(
-            Rect {
-                left: 0,
-                top: 0,
-                width: self.width,
-                height: self.height,
-            },
-            Color::BACKGROUND,
-        );
+        if self.dirty_repaint.is_empty() {
+            return;
+        }
+        let background = self.context_ref.theme.background;
+        for rect in core::mem::take(&mut self.dirty_repaint) {
+            self.draw_rect(rect, background);
+        }
+    }
+
+    /// Mark `rect` as changed this frame so the alternate buffer repaints it on
+    /// its next present. Called internally by [`Self::draw_rect`].
+    pub fn mark_dirty(&self, rect: Rect) {
+        self.context_ref.dirty.borrow_mut().mark(rect);
+    }
+
+    /// Finalize the frame drawn into this slot, propagating its changes to the
+    /// other buffer's pending set. Call once the frame has been presented.
+    pub fn commit_dirty(&self) {
+        self.context_ref.dirty.borrow_mut().commit((self.slot as usize) & 1);
+    }
+
+    /// Push a scissoring rectangle, intersected with any currently active clip,
+    /// so that subsequent draws are confined to it. Paired with [`Self::pop_clip`].
+    pub fn push_clip(&self, mut rect: Rect) {
+        let mut stack = self.context_ref.scisoring_config.borrow_mut();
+        if let Some(&current) = stack.last() {
+            rect = rect.intersect(current);
+        }
+        stack.push(rect);
+    }
+
+    /// Pop the most recently pushed scissoring rectangle.
+    pub fn pop_clip(&self) {
+        self.context_ref.scisoring_config.borrow_mut().pop();
     }
 
     pub fn draw_rect(&mut self, mut rect: Rect, color: Color) {
-        if let Some(&scisoring_area) = self.context_ref.scisoring_config.last() {
-            rect.intersect(scisoring_area);
+        if let Some(&scisoring_area) = self.context_ref.scisoring_config.borrow().last() {
+            rect = rect.intersect(scisoring_area);
         }
 
         if rect.is_empty() {
             return;
         }
 
+        self.mark_dirty(rect);
+
         for x_pixel in rect.left..rect.right() {
             for y_pixel in rect.top..rect.bottom() {
                 self.buffer[y_pixel * self.stride_item_count() + x_pixel].blend_with(color, true);
@@ -335,6 +1041,122 @@ impl<'b> FrameBuffer<'b> {
         }
     }
 
+    /// Rasterize `text` at `scale` pixels with the pen's top-left at `pos`,
+    /// blending each glyph into the RGBA4444 buffer through the renderer's
+    /// font fallback chain and glyph cache. Honours the active scissoring rect
+    /// and returns the laid-out bounding [`Rect`].
+    pub fn draw_text(&mut self, text: &str, pos: (usize, usize), scale: f32, color: Color) -> Rect {
+        let scale = Scale::uniform(scale);
+        let renderer = self.context_ref;
+        let line_advance = renderer.line_advance(scale);
+        let ascent = renderer.ascent(scale);
+        let descent = renderer.descent(scale);
+
+        let clip = renderer.scisoring_config.borrow().last().copied();
+        let stride = self.stride_item_count();
+
+        let origin_x = pos.0 as f32;
+        let mut pen_x = origin_x;
+        let mut baseline = pos.1 as f32 + ascent;
+        let mut max_x = origin_x;
+
+        for c in text.chars() {
+            if c == '\n' {
+                pen_x = origin_x;
+                baseline += line_advance;
+                continue;
+            }
+
+            renderer.with_glyph(c, scale, |glyph| {
+                let gx = pen_x as i32 + glyph.bearing_x;
+                let gy = baseline as i32 + glyph.bearing_y;
+
+                for row in 0..glyph.height {
+                    for col in 0..glyph.width {
+                        let cov = glyph.coverage[row * glyph.width + col];
+                        if cov == 0 {
+                            continue;
+                        }
+                        let px = gx + col as i32;
+                        let py = gy + row as i32;
+                        if px < 0 || py < 0 {
+                            continue;
+                        }
+                        let (px, py) = (px as usize, py as usize);
+                        if px >= self.width || py >= self.height {
+                            continue;
+                        }
+                        if let Some(clip) = clip {
+                            if !clip.contains(px, py) {
+                                continue;
+                            }
+                        }
+                        // Fold the glyph coverage into the colour's 4-bit alpha.
+                        let alpha = (cov as u16 * color.a() as u16 / 0xFF) as u8;
+                        if alpha == 0 {
+                            continue;
+                        }
+                        self.buffer[py * stride + px].blend_with(color.with_a(alpha), true);
+                    }
+                }
+
+                pen_x += glyph.advance;
+            });
+
+            if pen_x > max_x {
+                max_x = pen_x;
+            }
+        }
+
+        let bounds = Rect {
+            left: pos.0,
+            top: pos.1,
+            width: (max_x - origin_x) as usize,
+            // Extend past the last baseline by the descent so descenders
+            // (g/j/p/q/y) fall inside the reported — and thus repainted — bounds.
+            height: (baseline - pos.1 as f32 - descent) as usize,
+        };
+        // Let the dirty tracker repaint over the glyphs on the lagging buffer,
+        // just like the rect paths do, so scrolled-away or updated text can't
+        // ghost on the alternate swapchain slot.
+        self.mark_dirty(bounds);
+        bounds
+    }
+
+    /// Compute the bounding [`Rect`] `text` would occupy at `scale` without
+    /// drawing anything, for layout purposes.
+    pub fn measure_text(&self, text: &str, scale: f32) -> Rect {
+        let scale = Scale::uniform(scale);
+        let renderer = self.context_ref;
+        let line_advance = renderer.line_advance(scale);
+        let line_height = renderer.ascent(scale) - renderer.descent(scale);
+
+        let mut pen_x = 0f32;
+        let mut max_x = 0f32;
+        let mut lines = 1usize;
+
+        for c in text.chars() {
+            if c == '\n' {
+                pen_x = 0f32;
+                lines += 1;
+                continue;
+            }
+            renderer.with_glyph(c, scale, |glyph| pen_x += glyph.advance);
+            if pen_x > max_x {
+                max_x = pen_x;
+            }
+        }
+
+        Rect {
+            left: 0,
+            top: 0,
+            width: max_x as usize,
+            // Full cap-to-descender height for the last line, plus a baseline
+            // advance for each line above it.
+            height: (line_height + line_advance * (lines - 1) as f32) as usize,
+        }
+    }
+
     fn draw_box(&mut self, rect: Rect, line_width: usize, color: Color) {
         let line_offsets = core::cmp::max(1, line_width/2);
         //top
@@ -387,6 +1209,21 @@ pub mod elm {
     use super::*;
 
     pub trait Element {
+        /// Stable, per-instance identity used to route hit-tested input back to
+        /// this element. Defaults to the element's own address, which is stable
+        /// for the lifetime of the boxed tree.
+        fn id(&self) -> usize {
+            self as *const Self as *const () as usize
+        }
+
+        /// Register this element's hit region(s) for the current frame. The
+        /// default registers the element's own `bounds_rect`; containers should
+        /// override to also visit their children so that the topmost child wins
+        /// overlapping touches.
+        fn layout(&mut self, ctx: &mut LayoutCtx) {
+            ctx.push(self.id(), self.bounds_rect());
+        }
+
         fn request_focus(&mut self, focus_direction: FocusDirection) -> Option<&mut dyn Element> {None}
 
         fn on_click(&mut self, keys: u64) -> bool {
@@ -414,10 +1251,57 @@ pub mod elm {
             return false;
         }
 
+        /// A tap (short press-and-release) landed at `(x, y)`. Returns true if
+        /// consumed.
+        fn on_tap(&mut self, x: usize, y: usize) -> bool {
+            false
+        }
+
+        /// A long press fired at `(x, y)`. Returns true if consumed.
+        fn on_long_press(&mut self, x: usize, y: usize) -> bool {
+            false
+        }
+
+        /// A fling impulse is decaying with the given per-frame velocity.
+        /// Returns true if consumed.
+        fn on_fling(&mut self, vx: f32, vy: f32) -> bool {
+            false
+        }
+
+        /// Route a point gesture, already resolved by the renderer to the
+        /// hit-tested element `id`, to the element that owns that id. The
+        /// default applies it to this element when its own id matches;
+        /// containers override to recurse into their children in topmost-first
+        /// order so overlapping elements don't both respond.
+        fn handle_gesture(&mut self, id: usize, gesture: Gesture) -> bool {
+            if self.id() != id {
+                return false;
+            }
+            match gesture {
+                Gesture::Tap { x, y } => self.on_tap(x, y),
+                Gesture::LongPress { x, y } => self.on_long_press(x, y),
+                Gesture::Fling { vx, vy, .. } => self.on_fling(vx, vy),
+            }
+        }
+
         fn draw(&mut self, renderer: &mut FrameBuffer);
 
         fn bounds_rect(&self) -> Rect;
 
+        /// Reposition this element. Containers call this to lay their children
+        /// out and to translate them as the container scrolls; the default is a
+        /// no-op for elements whose position is fixed.
+        fn set_bounds(&mut self, bounds: Rect) {
+            let _ = bounds;
+        }
+
+        /// Per-instance colour override, consulted before falling back to the
+        /// theme. The default defers entirely to the active [`Theme`]; elements
+        /// that should paint in a fixed colour return it here.
+        fn override_color(&self) -> Option<Color> {
+            None
+        }
+
         fn draw_background(&self, framebuffer: &mut FrameBuffer, color: Option<Color>) {
             let Rect {
                 left,
@@ -425,14 +1309,19 @@ pub mod elm {
                 width,
                 height,
             } = self.bounds_rect();
-            framebuffer.draw_rect(self.bounds_rect(), color.unwrap_or(Color::BACKGROUND));
+            let color = color
+                .or_else(|| self.override_color())
+                .unwrap_or(framebuffer.context_ref.theme.background);
+            framebuffer.draw_rect(self.bounds_rect(), color);
         }
 
         fn draw_highlight(&self, framebuffer: &mut FrameBuffer, color: Option<Color>) {
             let Rect {
                 left, top, width, ..
             } = self.bounds_rect();
-            let color = color.unwrap_or(Color::HIGHLIGHT);
+            let color = color
+                .or_else(|| self.override_color())
+                .unwrap_or(framebuffer.context_ref.theme.highlight);
 
             framebuffer.draw_rect(
                 Rect {
@@ -487,6 +1376,10 @@ pub mod elm {
             self.bounds
         }
 
+        fn set_bounds(&mut self, bounds: Rect) {
+            self.bounds = bounds;
+        }
+
         fn draw(&mut self, renderer: &mut FrameBuffer) {
             renderer.draw_rect(self.bounds, self.color);
         }
@@ -498,8 +1391,8 @@ pub mod elm {
         color: Color,
         value: u8,
         icon: char,
-        value_changed_callback: Option<fn(u8)>
-        touch_locked: bool
+        value_changed_callback: Option<fn(u8)>,
+        touch_locked: bool,
     }
 
     impl TrackBar {
@@ -529,6 +1422,10 @@ pub mod elm {
             self.bounds
         }
 
+        fn set_bounds(&mut self, bounds: Rect) {
+            self.bounds = bounds;
+        }
+
         fn on_controller_input(
                     &mut self,
                     _new_keys: u64,
@@ -558,10 +1455,469 @@ pub mod elm {
                 (_,_) => false
             }
         }
+    }
 
-        
-        fn draw_background(&self, framebuffer: &mut FrameBuffer, color: Option<Color>) {
-            todo!()
+    /// Vertical, scrollable container that owns its children and moves focus
+    /// between them spatially. This is libtesla's central menu primitive.
+    pub struct List {
+        bounds: Rect,
+        parent: Option<Box<dyn Element>>,
+        children: Vec<Box<dyn Element>>,
+        /// Per-child layout rects in content coordinates (origin at the top of
+        /// the scrollable content, before translation by `scroll_offset`). Kept
+        /// parallel to `children`.
+        local: Vec<Rect>,
+        /// Total stacked height of the content laid out so far.
+        content_height: usize,
+        /// Index of the currently focused child, if any.
+        focused: Option<usize>,
+        /// Pixels the content is currently scrolled down by.
+        scroll_offset: usize,
+        /// Offset the scroll is animating toward.
+        target_offset: usize,
+        /// Each child's screen-space rect as of the last `draw`, kept parallel
+        /// to `children`, so a row that moves or gets scrolled out can have the
+        /// rect it vacated marked dirty alongside the one it moved to.
+        prev_screen: Vec<Rect>,
+    }
+
+    impl List {
+        /// Maximum pixels the scroll offset moves toward its target per frame.
+        const SCROLL_STEP: usize = 8;
+        /// Vertical gap inserted between stacked rows.
+        const ROW_SPACING: usize = 4;
+
+        pub fn new(bounds: Rect) -> Self {
+            Self {
+                bounds,
+                parent: None,
+                children: Vec::new(),
+                local: Vec::new(),
+                content_height: 0,
+                focused: None,
+                scroll_offset: 0,
+                target_offset: 0,
+                prev_screen: Vec::new(),
+            }
+        }
+
+        /// Append `child`, stacking it vertically below the previous rows at the
+        /// list's width. The child's incoming height is preserved; its position
+        /// is owned by the list from here on.
+        pub fn add_child(&mut self, child: Box<dyn Element>) {
+            let size = child.bounds_rect();
+            let local = Rect {
+                left: 0,
+                top: self.content_height,
+                width: self.bounds.width,
+                height: size.height,
+            };
+            self.content_height += size.height + Self::ROW_SPACING;
+            self.local.push(local);
+            self.children.push(child);
+            self.prev_screen.push(Rect::default());
+        }
+
+        /// Translate every child's content rect into screen space for the
+        /// current `scroll_offset` and apply it via `set_bounds`, so drawing,
+        /// the layout/hit-test pass and focus all see the scrolled positions.
+        /// Width is taken from the list's current bounds rather than the value
+        /// cached at `add_child` time, so a list resized after children were
+        /// added (e.g. by a parent container) reflows them to the new width.
+        fn reflow(&mut self) {
+            for (child, local) in self.children.iter_mut().zip(self.local.iter()) {
+                let top = (self.bounds.top + local.top).saturating_sub(self.scroll_offset);
+                child.set_bounds(Rect {
+                    left: self.bounds.left + local.left,
+                    top,
+                    width: self.bounds.width,
+                    height: local.height,
+                });
+            }
+        }
+
+        /// Pick the nearest child above (`upwards`) or below the child at
+        /// `current`: smallest vertical gap wins, ties broken by the greatest
+        /// horizontal overlap with the current row. Operates in content
+        /// coordinates so the result is stable regardless of scroll position.
+        fn nearest(&self, current: usize, upwards: bool) -> Option<usize> {
+            let cur = *self.local.get(current)?;
+            let mut best: Option<(usize, usize, usize)> = None; // (idx, gap, overlap)
+            for (idx, rect) in self.local.iter().enumerate() {
+                if idx == current {
+                    continue;
+                }
+                let gap = if upwards {
+                    if rect.bottom() > cur.top {
+                        continue;
+                    }
+                    cur.top - rect.bottom()
+                } else {
+                    if rect.top < cur.bottom() {
+                        continue;
+                    }
+                    rect.top - cur.bottom()
+                };
+                let overlap = cur
+                    .right()
+                    .min(rect.right())
+                    .saturating_sub(cur.left.max(rect.left));
+                let better = match best {
+                    None => true,
+                    Some((_, bgap, boverlap)) => gap < bgap || (gap == bgap && overlap > boverlap),
+                };
+                if better {
+                    best = Some((idx, gap, overlap));
+                }
+            }
+            best.map(|(idx, _, _)| idx)
+        }
+
+        /// Scroll so the child at `idx` is fully within the visible window.
+        fn ensure_visible(&mut self, idx: usize) {
+            let rect = self.local[idx];
+            if rect.top < self.target_offset {
+                self.target_offset = rect.top;
+            } else if rect.bottom() > self.target_offset + self.bounds.height {
+                self.target_offset = rect.bottom().saturating_sub(self.bounds.height);
+            }
+        }
+
+        /// Advance the scroll offset toward its target by at most one step.
+        fn step_scroll(&mut self) {
+            if self.scroll_offset < self.target_offset {
+                let delta = (self.target_offset - self.scroll_offset).min(Self::SCROLL_STEP);
+                self.scroll_offset += delta;
+            } else if self.scroll_offset > self.target_offset {
+                let delta = (self.scroll_offset - self.target_offset).min(Self::SCROLL_STEP);
+                self.scroll_offset -= delta;
+            }
+        }
+    }
+
+    impl Element for List {
+        fn bounds_rect(&self) -> Rect {
+            self.bounds
+        }
+
+        fn get_parent(&self) -> Option<&Box<dyn Element>> {
+            self.parent.as_ref()
+        }
+
+        fn set_parent(&mut self, parent: Box<dyn Element>) {
+            self.parent = Some(parent);
+        }
+
+        fn set_bounds(&mut self, bounds: Rect) {
+            self.bounds = bounds;
+        }
+
+        fn layout(&mut self, ctx: &mut LayoutCtx) {
+            // Settle the scroll offset first so the hitboxes registered here
+            // match where the rows are drawn later this same frame; otherwise a
+            // mid-animation touch could route to the wrong row.
+            self.step_scroll();
+            // Translate children into their scrolled positions before they
+            // register hitboxes, so input hit-tests against where rows actually
+            // appear this frame.
+            self.reflow();
+            ctx.push(self.id(), self.bounds);
+            for child in self.children.iter_mut() {
+                // Cull rows scrolled entirely out of the visible window.
+                let rect = child.bounds_rect();
+                if rect.bottom() < self.bounds.top || rect.top > self.bounds.bottom() {
+                    continue;
+                }
+                child.layout(ctx);
+            }
+        }
+
+        fn request_focus(&mut self, focus_direction: FocusDirection) -> Option<&mut dyn Element> {
+            if self.children.is_empty() {
+                return None;
+            }
+
+            let current = match self.focused {
+                Some(c) => c,
+                None => {
+                    // Entering the list: land on the edge nearest the direction
+                    // focus arrived from.
+                    let idx = match focus_direction {
+                        FocusDirection::Up => self.children.len() - 1,
+                        _ => 0,
+                    };
+                    self.focused = Some(idx);
+                    self.ensure_visible(idx);
+                    return self.children[idx].request_focus(focus_direction);
+                }
+            };
+
+            let next = match focus_direction {
+                FocusDirection::Up => self.nearest(current, true),
+                FocusDirection::Down => self.nearest(current, false),
+                _ => None,
+            };
+
+            match next {
+                Some(idx) => {
+                    self.focused = Some(idx);
+                    self.ensure_visible(idx);
+                    self.children[idx].request_focus(focus_direction)
+                }
+                None => {
+                    // Edge reached: shake the current row and let a parent take over.
+                    self.children[current].trigger_highlight_shake(focus_direction);
+                    None
+                }
+            }
+        }
+
+        fn handle_gesture(&mut self, id: usize, gesture: Gesture) -> bool {
+            // A fling's hit-tested id almost always belongs to the row under the
+            // release point, not the list itself, since rows are registered with
+            // a higher z than their list (see `layout`). Rows don't scroll
+            // themselves, so claim any fling landing within our own bounds here
+            // rather than letting it fall through to a child that would just
+            // drop it via the default `on_fling`.
+            if let Gesture::Fling { x, y, vx, vy } = gesture {
+                if self.bounds.contains(x, y) {
+                    return self.on_fling(vx, vy);
+                }
+            }
+            // Children are registered after the list itself in the layout pass,
+            // so they sit on top; search them topmost-first and let the renderer's
+            // hit-test id decide the single recipient.
+            for child in self.children.iter_mut().rev() {
+                if child.handle_gesture(id, gesture) {
+                    return true;
+                }
+            }
+            if self.id() == id {
+                return match gesture {
+                    Gesture::Tap { x, y } => self.on_tap(x, y),
+                    Gesture::LongPress { x, y } => self.on_long_press(x, y),
+                    Gesture::Fling { vx, vy, .. } => self.on_fling(vx, vy),
+                };
+            }
+            false
         }
+
+        fn on_fling(&mut self, _vx: f32, vy: f32) -> bool {
+            // Consume vertical momentum by steering the scroll target; a
+            // downward fling (positive vy) reveals content above.
+            let max_scroll = self.content_height.saturating_sub(self.bounds.height) as i32;
+            let next = self.target_offset as i32 - vy as i32;
+            self.target_offset = next.clamp(0, max_scroll) as usize;
+            true
+        }
+
+        fn draw(&mut self, renderer: &mut FrameBuffer) {
+            // `layout()` already stepped and reflowed this frame; reflow again in
+            // case this element is drawn without a preceding layout pass.
+            self.reflow();
+            renderer.push_clip(self.bounds);
+
+            for (idx, child) in self.children.iter_mut().enumerate() {
+                let rect = child.bounds_rect();
+                // A row that scrolled elsewhere, or got culled, vacates the rect
+                // it drew into last frame; nothing else repaints that strip, so
+                // mark it dirty here. Its new rect is marked by its own draw.
+                let prev = self.prev_screen[idx];
+                if prev != rect {
+                    renderer.mark_dirty(prev);
+                }
+                self.prev_screen[idx] = rect;
+
+                // Cull rows scrolled entirely out of the visible window.
+                if rect.bottom() < self.bounds.top || rect.top > self.bounds.bottom() {
+                    continue;
+                }
+                child.draw(renderer);
+            }
+
+            renderer.pop_clip();
+        }
+    }
+
+    /// Type tags used by the serialized layout format to select a constructor
+    /// from the [`Loader`] registry.
+    pub mod tags {
+        pub const DEBUG_RECT: u8 = 0;
+        pub const TRACK_BAR: u8 = 1;
+        pub const LIST: u8 = 2;
+    }
+
+    /// A single decoded node from a serialized layout tree. Borrows its text
+    /// from the source byte slice.
+    pub struct NodeDesc<'a> {
+        pub tag: u8,
+        pub bounds: Rect,
+        pub color: Color,
+        pub text: &'a str,
+        pub icon: char,
+        pub value: u8,
+        pub callback_id: u16,
+        pub children: Vec<NodeDesc<'a>>,
+    }
+
+    /// Constructor turning a decoded node into a live element. Containers use
+    /// the supplied `&Loader` to recursively build their children.
+    pub type Constructor = fn(&NodeDesc, &Loader) -> Box<dyn Element>;
+
+    /// Registry of element constructors plus the host's callback bindings, used
+    /// to instantiate a `Box<dyn Element>` tree from a layout data file. This
+    /// lets one overlay binary render many menus shipped as data.
+    pub struct Loader {
+        registry: BTreeMap<u8, Constructor>,
+        callbacks: BTreeMap<u16, fn(u8)>,
+    }
+
+    impl Default for Loader {
+        fn default() -> Self {
+            let mut registry = BTreeMap::new();
+            registry.insert(tags::DEBUG_RECT, build_debug_rect as Constructor);
+            registry.insert(tags::TRACK_BAR, build_track_bar as Constructor);
+            registry.insert(tags::LIST, build_list as Constructor);
+            Self {
+                registry,
+                callbacks: BTreeMap::new(),
+            }
+        }
+    }
+
+    impl Loader {
+        /// A loader with only the built-in element types registered.
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Register a constructor for a custom type tag, overriding any existing
+        /// entry for that tag.
+        pub fn register(&mut self, tag: u8, constructor: Constructor) {
+            self.registry.insert(tag, constructor);
+        }
+
+        /// Bind an integer callback id referenced by the layout to a real
+        /// handler provided by the host app.
+        pub fn bind(&mut self, id: u16, handler: fn(u8)) {
+            self.callbacks.insert(id, handler);
+        }
+
+        /// Resolve a callback id to its bound handler, if any.
+        pub fn callback(&self, id: u16) -> Option<fn(u8)> {
+            self.callbacks.get(&id).copied()
+        }
+
+        /// Instantiate a single decoded node via its registered constructor.
+        pub fn build(&self, desc: &NodeDesc) -> Option<Box<dyn Element>> {
+            let constructor = self.registry.get(&desc.tag)?;
+            Some(constructor(desc, self))
+        }
+
+        /// Parse a serialized layout tree from `data` and build the root element.
+        pub fn load(&self, data: &[u8]) -> Option<Box<dyn Element>> {
+            let mut pos = 0;
+            let root = parse_node(data, &mut pos, MAX_LAYOUT_DEPTH)?;
+            self.build(&root)
+        }
+    }
+
+    fn build_debug_rect(desc: &NodeDesc, _loader: &Loader) -> Box<dyn Element> {
+        Box::new(DebugRectangle::new(desc.color, desc.bounds))
+    }
+
+    fn build_track_bar(desc: &NodeDesc, loader: &Loader) -> Box<dyn Element> {
+        Box::new(TrackBar::new(
+            desc.bounds,
+            desc.color,
+            Some(desc.value),
+            desc.icon,
+            loader.callback(desc.callback_id),
+            false,
+        ))
+    }
+
+    fn build_list(desc: &NodeDesc, loader: &Loader) -> Box<dyn Element> {
+        let mut list = List::new(desc.bounds);
+        for child in &desc.children {
+            if let Some(element) = loader.build(child) {
+                list.add_child(element);
+            }
+        }
+        Box::new(list)
+    }
+
+    fn read_u8(data: &[u8], pos: &mut usize) -> Option<u8> {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        Some(byte)
+    }
+
+    fn read_u16(data: &[u8], pos: &mut usize) -> Option<u16> {
+        let bytes = data.get(*pos..*pos + 2)?;
+        *pos += 2;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(data: &[u8], pos: &mut usize) -> Option<u32> {
+        let bytes = data.get(*pos..*pos + 4)?;
+        *pos += 4;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Greatest nesting depth a layout tree may declare. Layout files are
+    /// loaded as untrusted data, so this bounds the parser's recursion against a
+    /// hand-crafted deeply-nested file.
+    const MAX_LAYOUT_DEPTH: usize = 32;
+
+    /// Decode one node and, recursively, its declared children. The wire layout
+    /// is: tag `u8`, bounds (four `u32` as left/top/width/height), colour `u16`,
+    /// callback id `u16`, icon codepoint `u32`, value `u8`, UTF-8 text prefixed
+    /// by its `u16` byte length, then a `u16` child count followed by that many
+    /// nodes. All integers are little-endian.
+    ///
+    /// `depth` is the remaining nesting budget; parsing fails once it is
+    /// exhausted. The declared child count is never trusted for pre-allocation —
+    /// children are appended as their bytes validate — so a bogus count can't
+    /// force a large transient allocation.
+    fn parse_node<'a>(data: &'a [u8], pos: &mut usize, depth: usize) -> Option<NodeDesc<'a>> {
+        if depth == 0 {
+            return None;
+        }
+        let tag = read_u8(data, pos)?;
+        let bounds = Rect {
+            left: read_u32(data, pos)? as usize,
+            top: read_u32(data, pos)? as usize,
+            width: read_u32(data, pos)? as usize,
+            height: read_u32(data, pos)? as usize,
+        };
+        let color = Color::from_bits(read_u16(data, pos)?);
+        let callback_id = read_u16(data, pos)?;
+        let icon = char::from_u32(read_u32(data, pos)?).unwrap_or('\0');
+        let value = read_u8(data, pos)?;
+
+        let text_len = read_u16(data, pos)? as usize;
+        let text_bytes = data.get(*pos..*pos + text_len)?;
+        *pos += text_len;
+        let text = core::str::from_utf8(text_bytes).ok()?;
+
+        let child_count = read_u16(data, pos)? as usize;
+        // Grow as children validate rather than trusting the declared count up
+        // front, so an inflated count can't force a multi-MB allocation.
+        let mut children = Vec::new();
+        for _ in 0..child_count {
+            children.push(parse_node(data, pos, depth - 1)?);
+        }
+
+        Some(NodeDesc {
+            tag,
+            bounds,
+            color,
+            text,
+            icon,
+            value,
+            callback_id,
+            children,
+        })
     }
 }